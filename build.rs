@@ -0,0 +1,188 @@
+//! Builds TensorFlow Lite C library with Bazel and generates bindings with bindgen.
+//!
+//! Set `TFLITEC_PREBUILT_LIB_DIR` (and optionally `TFLITEC_HEADER_DIR`) to skip the Bazel
+//! build entirely and link against an already-built `libtensorflowlite_c` instead, e.g. one
+//! produced by CMake, a system package, or a CI cache.
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const TENSORFLOW_BRANCH: &str = "r2.6";
+
+/// The `custom_op` feature registers ops through the "opaque" operator C API
+/// (`tensorflow/lite/core/c/operator.h`, `TfLiteOperatorCreate` and the `*WithData` setters),
+/// which landed well after `r2.6` and also after `r2.10` (which still only had the older
+/// `TfLiteRegistrationExternal` naming); `r2.13` is the first branch known to have the API in
+/// its current shape.
+const TENSORFLOW_BRANCH_CUSTOM_OP: &str = "r2.13";
+
+fn main() {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let tensorflow_branch = if cfg!(feature = "custom_op") {
+        TENSORFLOW_BRANCH_CUSTOM_OP
+    } else {
+        TENSORFLOW_BRANCH
+    };
+
+    let header_dir = if let Ok(lib_dir) = env::var("TFLITEC_PREBUILT_LIB_DIR") {
+        link_prebuilt(Path::new(&lib_dir))
+    } else {
+        let tensorflow_dir = fetch_tensorflow(&out_dir, tensorflow_branch);
+        bazel_build(&tensorflow_dir);
+        println!(
+            "cargo:rustc-link-search=native={}",
+            tensorflow_dir
+                .join("bazel-bin/tensorflow/lite/c")
+                .display()
+        );
+        println!("cargo:rustc-link-lib=dylib=tensorflowlite_c");
+        tensorflow_dir
+    };
+
+    generate_bindings(&header_dir, &out_dir);
+}
+
+/// Links against a prebuilt `libtensorflowlite_c` instead of invoking Bazel, returning the
+/// directory bindgen should search for headers in.
+fn link_prebuilt(lib_dir: &Path) -> PathBuf {
+    println!("cargo:rustc-link-search=native={}", lib_dir.display());
+    println!("cargo:rustc-link-lib=dylib=tensorflowlite_c");
+
+    env::var("TFLITEC_HEADER_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| lib_dir.to_path_buf())
+}
+
+/// Clones the `tensorflow` project at `branch` into `OUT_DIR` unless it is already present
+/// from a previous build.
+fn fetch_tensorflow(out_dir: &Path, branch: &str) -> PathBuf {
+    let tensorflow_dir = out_dir.join("tensorflow");
+    if !tensorflow_dir.join("WORKSPACE").exists() {
+        let status = Command::new("git")
+            .args(&[
+                "clone",
+                "--branch",
+                branch,
+                "--depth",
+                "1",
+                "https://github.com/tensorflow/tensorflow.git",
+            ])
+            .arg(&tensorflow_dir)
+            .status()
+            .expect("failed to run git, is it installed?");
+        assert!(status.success(), "failed to clone tensorflow");
+    }
+    tensorflow_dir
+}
+
+/// Invokes Bazel to build `libtensorflowlite_c`, enabling XNNPACK as the `xnnpack*` features
+/// ask and forwarding `BAZEL_COPTS` as `--copt` flags.
+fn bazel_build(tensorflow_dir: &Path) {
+    let mut cmd = Command::new("bazel");
+    cmd.current_dir(tensorflow_dir)
+        .arg("build")
+        .arg("-c")
+        .arg("opt")
+        .arg("//tensorflow/lite/c:tensorflowlite_c");
+
+    if cfg!(feature = "xnnpack") {
+        cmd.arg("--define").arg("tflite_with_xnnpack=true");
+    }
+    if cfg!(feature = "xnnpack_qs8") {
+        cmd.arg("--define").arg("xnn_enable_qs8=true");
+    }
+    if cfg!(feature = "xnnpack_qu8") {
+        cmd.arg("--define").arg("xnn_enable_qu8=true");
+    }
+
+    if let Ok(copts) = env::var("BAZEL_COPTS") {
+        for copt in copts.split_whitespace() {
+            cmd.arg(format!("--copt={}", copt));
+        }
+    }
+
+    let status = cmd.status().expect("failed to run bazel, is it installed?");
+    assert!(status.success(), "bazel build failed");
+}
+
+/// Runs bindgen against the TF Lite C headers under `header_dir`, producing
+/// `OUT_DIR/bindings.rs`.
+fn generate_bindings(header_dir: &Path, out_dir: &Path) {
+    let mut builder = bindgen::Builder::default()
+        .header(
+            header_dir
+                .join("tensorflow/lite/c/c_api.h")
+                .to_str()
+                .unwrap(),
+        )
+        .header(
+            header_dir
+                .join("tensorflow/lite/c/c_api_experimental.h")
+                .to_str()
+                .unwrap(),
+        )
+        .clang_arg(format!("-I{}", header_dir.display()));
+
+    if cfg!(feature = "gpu_delegate") {
+        builder = builder.header(
+            header_dir
+                .join("tensorflow/lite/delegates/gpu/delegate.h")
+                .to_str()
+                .unwrap(),
+        );
+    }
+    if cfg!(feature = "nnapi_delegate") {
+        builder = builder.header(
+            header_dir
+                .join("tensorflow/lite/delegates/nnapi/nnapi_delegate.h")
+                .to_str()
+                .unwrap(),
+        );
+    }
+    if cfg!(feature = "coreml_delegate") {
+        builder = builder.header(
+            header_dir
+                .join("tensorflow/lite/delegates/coreml/coreml_delegate.h")
+                .to_str()
+                .unwrap(),
+        );
+    }
+    builder = builder.header(
+        header_dir
+            .join("tensorflow/lite/delegates/external/external_delegate.h")
+            .to_str()
+            .unwrap(),
+    );
+    if cfg!(feature = "xnnpack") {
+        builder = builder.header(
+            header_dir
+                .join("tensorflow/lite/delegates/xnnpack/xnnpack_delegate.h")
+                .to_str()
+                .unwrap(),
+        );
+    }
+    if cfg!(feature = "custom_op") {
+        builder = builder.header(
+            header_dir
+                .join("tensorflow/lite/core/c/operator.h")
+                .to_str()
+                .unwrap(),
+        );
+        builder = builder.header(
+            header_dir
+                .join("tensorflow/lite/core/c/c_api_opaque.h")
+                .to_str()
+                .unwrap(),
+        );
+    }
+
+    let bindings = builder
+        .allowlist_function("TfLite.*")
+        .allowlist_type("TfLite.*")
+        .generate()
+        .expect("unable to generate bindings");
+
+    bindings
+        .write_to_file(out_dir.join("bindings.rs"))
+        .expect("couldn't write bindings");
+}
@@ -0,0 +1,298 @@
+//! Custom operator registration, for models containing ops that are not in the builtin op
+//! resolver (e.g. Larq Compute Engine binary ops), so `allocate_tensors` does not fail on
+//! them.
+use crate::bindings::*;
+use crate::error::{Error, ErrorKind, Result};
+use std::ffi::CString;
+use std::os::raw::c_void;
+use std::slice;
+
+/// Implemented by a custom operator's kernel and registered with
+/// [`Options::add_custom_op`](crate::interpreter::Options::add_custom_op) so that models
+/// containing this op can be loaded and run.
+///
+/// A model may contain more than one node of the same custom op (e.g. several binary
+/// convolution layers); [`CustomOp::new`] takes a factory that produces one `CustomOpKernel`
+/// per node, so each node's state is independent.
+pub trait CustomOpKernel: Send {
+    /// Called once when the node is created, with the op's raw init data (the `options` bytes
+    /// baked into the model's flatbuffer for this op). The default implementation ignores it.
+    fn init(&mut self, _init_data: &[u8]) {}
+
+    /// Called whenever input shapes are known or change. The default implementation does
+    /// nothing, which is correct for ops whose output shape does not depend on input shapes.
+    fn prepare(&mut self, _node: &OpNode) -> Result<()> {
+        Ok(())
+    }
+
+    /// Computes this op's outputs from its inputs.
+    fn invoke(&mut self, node: &OpNode) -> Result<()>;
+}
+
+type KernelFactory = dyn Fn() -> Box<dyn CustomOpKernel> + Send + Sync;
+
+/// A custom operator, ready to be registered via
+/// [`Options::add_custom_op`](crate::interpreter::Options::add_custom_op).
+///
+/// Wraps a kernel factory behind the `TfLiteOperator`/`TfLiteInterpreterOptionsAddCustomOp` C
+/// API, so that the op's `init`/`prepare`/`invoke`/`free` callbacks run the corresponding trait
+/// methods on a fresh [`CustomOpKernel`] per node instance.
+pub struct CustomOp {
+    pub(crate) registration: *mut TfLiteOperator,
+    factory: *mut Box<KernelFactory>,
+}
+
+impl CustomOp {
+    /// Creates the custom op named `name` (matching the op name baked into the model's
+    /// flatbuffer), at the given `version`. `make_kernel` is called once per node of this op
+    /// found in the model, so that every node gets its own, independent `CustomOpKernel`.
+    pub fn new(
+        name: &str,
+        version: i32,
+        make_kernel: impl Fn() -> Box<dyn CustomOpKernel> + Send + Sync + 'static,
+    ) -> CustomOp {
+        let name = CString::new(name).expect("custom op name has interior NUL");
+        let factory: *mut Box<KernelFactory> =
+            Box::into_raw(Box::new(Box::new(make_kernel) as Box<KernelFactory>));
+        unsafe {
+            let registration = TfLiteOperatorCreate(
+                TfLiteBuiltinOperator_kTfLiteBuiltinCustom,
+                name.as_ptr(),
+                version,
+            );
+            TfLiteOperatorSetUserData(registration, factory as *mut c_void);
+            TfLiteOperatorSetInitWithData(registration, Some(init_trampoline));
+            TfLiteOperatorSetPrepareWithData(registration, Some(prepare_trampoline));
+            TfLiteOperatorSetInvokeWithData(registration, Some(invoke_trampoline));
+            TfLiteOperatorSetFreeWithData(registration, Some(free_trampoline));
+            CustomOp {
+                registration,
+                factory,
+            }
+        }
+    }
+}
+
+impl Drop for CustomOp {
+    fn drop(&mut self) {
+        unsafe {
+            TfLiteOperatorDelete(self.registration);
+            drop(Box::from_raw(self.factory));
+        }
+    }
+}
+
+unsafe impl Send for CustomOp {}
+
+/// Safe access to a custom op's inputs and outputs during [`CustomOpKernel::prepare`] and
+/// [`CustomOpKernel::invoke`].
+pub struct OpNode<'a> {
+    context: *mut TfLiteOpaqueContext,
+    node: *mut TfLiteOpaqueNode,
+    _marker: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> OpNode<'a> {
+    /// The number of input tensors to this op.
+    pub fn input_count(&self) -> usize {
+        unsafe { TfLiteOpaqueNodeNumberOfInputs(self.node) as usize }
+    }
+
+    /// The number of output tensors of this op.
+    pub fn output_count(&self) -> usize {
+        unsafe { TfLiteOpaqueNodeNumberOfOutputs(self.node) as usize }
+    }
+
+    /// The input tensor at `index`.
+    pub fn input(&self, index: usize) -> Result<OpaqueTensor<'a>> {
+        unsafe {
+            let tensor = TfLiteOpaqueNodeGetInput(self.context, self.node, index as i32);
+            if tensor.is_null() {
+                Err(Error::new(ErrorKind::FailedToGetTensor(index)))
+            } else {
+                Ok(OpaqueTensor::new(tensor))
+            }
+        }
+    }
+
+    /// Copies `data` into the output tensor at `index`.
+    pub fn set_output<T>(&self, index: usize, data: &[T]) -> Result<()> {
+        unsafe {
+            let tensor = TfLiteOpaqueNodeGetOutput(self.context, self.node, index as i32);
+            if tensor.is_null() {
+                return Err(Error::new(ErrorKind::FailedToGetTensor(index)));
+            }
+            let byte_size = std::mem::size_of_val(data);
+            let status = TfLiteOpaqueTensorCopyFromBuffer(
+                tensor,
+                data.as_ptr() as *const c_void,
+                byte_size,
+            );
+            if status == TfLiteStatus_kTfLiteOk {
+                Ok(())
+            } else {
+                Err(Error::new(ErrorKind::FailedToCopyDataToInputTensor))
+            }
+        }
+    }
+}
+
+/// An input tensor of an [`OpNode`], accessed through the TF Lite "opaque" tensor API.
+///
+/// Unlike [`Tensor`](crate::tensor::Tensor), this wraps a `TfLiteOpaqueTensor`, which is a
+/// distinct, incompatible C type from the classic `TfLiteTensor` used elsewhere in this crate.
+pub struct OpaqueTensor<'a> {
+    tensor: *const TfLiteOpaqueTensor,
+    _marker: std::marker::PhantomData<&'a ()>,
+}
+
+impl<'a> OpaqueTensor<'a> {
+    fn new(tensor: *const TfLiteOpaqueTensor) -> OpaqueTensor<'a> {
+        OpaqueTensor {
+            tensor,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// The contents of this tensor, reinterpreted as a slice of `T`.
+    ///
+    /// # Panics
+    /// Panics if the byte size of this tensor's buffer is not a multiple of `size_of::<T>()`.
+    pub fn data<T>(&self) -> &'a [T] {
+        unsafe {
+            let data = TfLiteOpaqueTensorData(self.tensor) as *const T;
+            let byte_size = TfLiteOpaqueTensorByteSize(self.tensor);
+            assert_eq!(
+                byte_size % std::mem::size_of::<T>(),
+                0,
+                "tensor byte size is not a multiple of the element size"
+            );
+            let count = byte_size / std::mem::size_of::<T>();
+            slice::from_raw_parts(data, count)
+        }
+    }
+}
+
+/// Creates this node's per-node kernel state from the registration-level `factory`, applying
+/// `init_data` to it. Pulled out of [`init_trampoline`] so the node-state lifecycle can be unit
+/// tested without a real TF Lite runtime to drive the `extern "C"` callbacks.
+fn make_node_state(factory: &KernelFactory, init_data: &[u8]) -> *mut Box<dyn CustomOpKernel> {
+    let mut kernel = factory();
+    kernel.init(init_data);
+    Box::into_raw(Box::new(kernel))
+}
+
+unsafe extern "C" fn init_trampoline(
+    user_data: *mut c_void,
+    _context: *mut TfLiteOpaqueContext,
+    buffer: *const std::os::raw::c_char,
+    length: usize,
+) -> *mut c_void {
+    let factory = &*(user_data as *mut Box<KernelFactory>);
+    let init_data = slice::from_raw_parts(buffer as *const u8, length);
+    make_node_state(factory, init_data) as *mut c_void
+}
+
+unsafe extern "C" fn prepare_trampoline(
+    _user_data: *mut c_void,
+    context: *mut TfLiteOpaqueContext,
+    node: *mut TfLiteOpaqueNode,
+) -> TfLiteStatus {
+    let kernel = &mut *(TfLiteOpaqueNodeGetUserData(node) as *mut Box<dyn CustomOpKernel>);
+    let op_node = OpNode {
+        context,
+        node,
+        _marker: std::marker::PhantomData,
+    };
+    match kernel.prepare(&op_node) {
+        Ok(()) => TfLiteStatus_kTfLiteOk,
+        Err(_) => TfLiteStatus_kTfLiteError,
+    }
+}
+
+unsafe extern "C" fn invoke_trampoline(
+    _user_data: *mut c_void,
+    context: *mut TfLiteOpaqueContext,
+    node: *mut TfLiteOpaqueNode,
+) -> TfLiteStatus {
+    let kernel = &mut *(TfLiteOpaqueNodeGetUserData(node) as *mut Box<dyn CustomOpKernel>);
+    let op_node = OpNode {
+        context,
+        node,
+        _marker: std::marker::PhantomData,
+    };
+    match kernel.invoke(&op_node) {
+        Ok(()) => TfLiteStatus_kTfLiteOk,
+        Err(_) => TfLiteStatus_kTfLiteError,
+    }
+}
+
+/// Drops the per-node kernel state returned by `init_trampoline`/[`make_node_state`]. `buffer`
+/// is that per-node state, not the registration-level `user_data`.
+unsafe extern "C" fn free_trampoline(_user_data: *mut c_void, buffer: *mut c_void) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(buffer as *mut Box<dyn CustomOpKernel>));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A kernel with its own private counter, plus a shared log every kernel appends its
+    /// counter's value to after incrementing it. If two nodes shared one kernel instance, the
+    /// logged values would keep climbing across nodes instead of restarting at 1 for each.
+    struct CountingKernel {
+        local_count: usize,
+        log: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl CustomOpKernel for CountingKernel {
+        fn invoke(&mut self, _node: &OpNode) -> Result<()> {
+            self.local_count += 1;
+            self.log.lock().unwrap().push(self.local_count);
+            Ok(())
+        }
+    }
+
+    fn dummy_op_node<'a>() -> OpNode<'a> {
+        OpNode {
+            context: std::ptr::null_mut(),
+            node: std::ptr::null_mut(),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[test]
+    fn make_node_state_creates_independent_kernels_per_node() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let factory: Box<KernelFactory> = {
+            let log = Arc::clone(&log);
+            Box::new(move || {
+                Box::new(CountingKernel {
+                    local_count: 0,
+                    log: Arc::clone(&log),
+                }) as Box<dyn CustomOpKernel>
+            })
+        };
+
+        let node_a = make_node_state(&*factory, &[]);
+        let node_b = make_node_state(&*factory, &[]);
+
+        unsafe {
+            (*node_a).invoke(&dummy_op_node()).unwrap();
+            (*node_a).invoke(&dummy_op_node()).unwrap();
+            (*node_b).invoke(&dummy_op_node()).unwrap();
+
+            drop(Box::from_raw(node_a));
+            drop(Box::from_raw(node_b));
+        }
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![1, 2, 1],
+            "node_b's kernel must start from its own fresh state, not share node_a's"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Hardware acceleration delegates that can be attached to an
+//! [`Interpreter`](crate::interpreter::Interpreter) via
+//! [`Options::add_delegate`](crate::interpreter::Options::add_delegate).
+//!
+//! Each bundled delegate kind is gated behind its own Cargo feature, which adds that
+//! delegate's C header to the generated bindings. These features do not change the Bazel
+//! build, which builds only the `tensorflowlite_c` target, so the delegate's functions are
+//! available only if the linked TF Lite library contains them:
+//! * `gpu_delegate` - the GPU delegate (OpenGL/OpenCL/Metal depending on platform).
+//! * `nnapi_delegate` - the Android NNAPI delegate.
+//! * `coreml_delegate` - the iOS Core ML delegate.
+use crate::bindings::TfLiteDelegate;
+use crate::error::Result;
+use std::path::Path;
+
+#[cfg(feature = "gpu_delegate")]
+mod gpu;
+#[cfg(feature = "nnapi_delegate")]
+mod nnapi;
+#[cfg(feature = "coreml_delegate")]
+mod coreml;
+mod external;
+
+#[cfg(feature = "nnapi_delegate")]
+pub use nnapi::{NnApiExecutionPreference, NnApiOptions};
+
+/// A hardware acceleration delegate.
+///
+/// A `Delegate` is consumed by [`Options::add_delegate`](crate::interpreter::Options::add_delegate)
+/// and the underlying `TfLiteDelegate` is kept alive for as long as the
+/// [`Interpreter`](crate::interpreter::Interpreter) that was built from it, since the interpreter
+/// borrows it for the lifetime of inference.
+pub enum Delegate {
+    /// GPU delegate. Requires the `gpu_delegate` feature.
+    #[cfg(feature = "gpu_delegate")]
+    Gpu,
+    /// Android NNAPI delegate. Requires the `nnapi_delegate` feature.
+    #[cfg(feature = "nnapi_delegate")]
+    NnApi(NnApiOptions),
+    /// iOS Core ML delegate. Requires the `coreml_delegate` feature.
+    #[cfg(feature = "coreml_delegate")]
+    CoreMl,
+    /// A delegate loaded at runtime from an external shared library, see
+    /// [`Delegate::external`].
+    External(RawDelegate),
+}
+
+impl Delegate {
+    /// Loads a third-party delegate from the shared library at `path` (e.g. an Arm NN or
+    /// vendor NPU delegate), configured with `options` key/value pairs understood by that
+    /// delegate. This works for any delegate that implements TF Lite's
+    /// `external_delegate.h` C API, without the crate needing to be recompiled against it.
+    pub fn external(path: &Path, options: &[(&str, &str)]) -> Result<Delegate> {
+        external::create(path, options).map(Delegate::External)
+    }
+
+    pub(crate) fn into_raw(self) -> Result<RawDelegate> {
+        match self {
+            #[cfg(feature = "gpu_delegate")]
+            Delegate::Gpu => gpu::create(),
+            #[cfg(feature = "nnapi_delegate")]
+            Delegate::NnApi(options) => nnapi::create(&options),
+            #[cfg(feature = "coreml_delegate")]
+            Delegate::CoreMl => coreml::create(),
+            Delegate::External(raw) => Ok(raw),
+        }
+    }
+}
+
+/// An owned, type-erased `TfLiteDelegate*` together with the function that must be used to
+/// free it. Kept alive by the [`Interpreter`](crate::interpreter::Interpreter) that was built
+/// with it, and deleted only after the interpreter itself has been destroyed.
+pub struct RawDelegate {
+    pub(crate) ptr: *mut TfLiteDelegate,
+    delete: unsafe extern "C" fn(*mut TfLiteDelegate),
+}
+
+impl RawDelegate {
+    pub(crate) fn new(
+        ptr: *mut TfLiteDelegate,
+        delete: unsafe extern "C" fn(*mut TfLiteDelegate),
+    ) -> RawDelegate {
+        RawDelegate { ptr, delete }
+    }
+}
+
+impl Drop for RawDelegate {
+    fn drop(&mut self) {
+        unsafe { (self.delete)(self.ptr) }
+    }
+}
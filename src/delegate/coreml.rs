@@ -0,0 +1,16 @@
+//! iOS Core ML delegate creation, compiled when the `coreml_delegate` feature is enabled.
+use super::RawDelegate;
+use crate::bindings::{TfLiteCoreMlDelegateCreate, TfLiteCoreMlDelegateDelete};
+use crate::error::{Error, ErrorKind, Result};
+
+pub(super) fn create() -> Result<RawDelegate> {
+    unsafe {
+        // A null options pointer tells the delegate to use its documented defaults.
+        let ptr = TfLiteCoreMlDelegateCreate(std::ptr::null());
+        if ptr.is_null() {
+            Err(Error::new(ErrorKind::FailedToCreateDelegate))
+        } else {
+            Ok(RawDelegate::new(ptr, TfLiteCoreMlDelegateDelete))
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Delegates loaded at runtime from a third-party shared library, via TF Lite's
+//! `external_delegate.h` API.
+use super::RawDelegate;
+use crate::bindings::{
+    TfLiteExternalDelegateCreate, TfLiteExternalDelegateDelete, TfLiteExternalDelegateOptions,
+    TfLiteExternalDelegateOptionsDefault, TfLiteExternalDelegateOptionsInsert,
+};
+use std::ffi::CString;
+use std::path::Path;
+
+/// Loads an external delegate from the shared library at `path`, configuring it with
+/// `options` key/value pairs understood by that particular delegate.
+///
+/// The delegate library (e.g. an Arm NN or vendor NPU delegate) must already be present on
+/// the target device; this does not link against it at build time.
+pub(super) fn create(path: &Path, options: &[(&str, &str)]) -> crate::error::Result<RawDelegate> {
+    let path = path
+        .to_str()
+        .and_then(|p| CString::new(p).ok())
+        .ok_or_else(|| crate::error::Error::new(crate::error::ErrorKind::FailedToCreateDelegate))?;
+    unsafe {
+        let mut raw_options: TfLiteExternalDelegateOptions =
+            TfLiteExternalDelegateOptionsDefault(path.as_ptr());
+        // `TfLiteExternalDelegateOptionsInsert` copies the key/value strings internally, so
+        // the `CString`s only need to outlive the insert call.
+        for (key, value) in options {
+            let key = CString::new(*key).expect("option key has interior NUL");
+            let value = CString::new(*value).expect("option value has interior NUL");
+            TfLiteExternalDelegateOptionsInsert(&mut raw_options, key.as_ptr(), value.as_ptr());
+        }
+        let ptr = TfLiteExternalDelegateCreate(&raw_options);
+        if ptr.is_null() {
+            Err(crate::error::Error::new(
+                crate::error::ErrorKind::FailedToCreateDelegate,
+            ))
+        } else {
+            Ok(RawDelegate::new(ptr, TfLiteExternalDelegateDelete))
+        }
+    }
+}
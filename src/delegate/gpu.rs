@@ -0,0 +1,18 @@
+//! GPU delegate creation, compiled when the `gpu_delegate` feature is enabled.
+use super::RawDelegate;
+use crate::bindings::{
+    TfLiteGpuDelegateV2Create, TfLiteGpuDelegateV2Delete, TfLiteGpuDelegateOptionsV2Default,
+};
+use crate::error::{Error, ErrorKind, Result};
+
+pub(super) fn create() -> Result<RawDelegate> {
+    unsafe {
+        let mut options = TfLiteGpuDelegateOptionsV2Default();
+        let ptr = TfLiteGpuDelegateV2Create(&mut options);
+        if ptr.is_null() {
+            Err(Error::new(ErrorKind::FailedToCreateDelegate))
+        } else {
+            Ok(RawDelegate::new(ptr, TfLiteGpuDelegateV2Delete))
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Android NNAPI delegate creation, compiled when the `nnapi_delegate` feature is enabled.
+use super::RawDelegate;
+use crate::bindings::{
+    TfLiteNnapiDelegateCreate, TfLiteNnapiDelegateDelete, TfLiteNnapiDelegateOptionsDefault,
+    TfLiteNnapiDelegateOptions,
+};
+use crate::error::{Error, ErrorKind, Result};
+use std::ffi::CString;
+use std::os::raw::c_int;
+
+/// The NNAPI execution preference, mirroring `TfLiteNnapiExecutionPreference`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum NnApiExecutionPreference {
+    Undefined,
+    LowPower,
+    FastSingleAnswer,
+    SustainedSpeed,
+}
+
+impl NnApiExecutionPreference {
+    fn as_raw(self) -> c_int {
+        match self {
+            NnApiExecutionPreference::Undefined => 0,
+            NnApiExecutionPreference::LowPower => 1,
+            NnApiExecutionPreference::FastSingleAnswer => 2,
+            NnApiExecutionPreference::SustainedSpeed => 3,
+        }
+    }
+}
+
+/// Options controlling how the NNAPI delegate is created.
+#[derive(Debug, Clone, Default)]
+pub struct NnApiOptions {
+    /// Name of the accelerator to use, as reported by `ANeuralNetworks_getDeviceName`.
+    /// Leave as `None` to let NNAPI select the accelerator automatically.
+    pub accelerator_name: Option<String>,
+    /// Hint for how NNAPI should trade off latency against power usage.
+    pub execution_preference: Option<NnApiExecutionPreference>,
+}
+
+pub(super) fn create(options: &NnApiOptions) -> Result<RawDelegate> {
+    unsafe {
+        let mut raw_options: TfLiteNnapiDelegateOptions = TfLiteNnapiDelegateOptionsDefault();
+        if let Some(preference) = options.execution_preference {
+            raw_options.execution_preference = preference.as_raw();
+        }
+        // `TfLiteNnapiDelegateCreate` copies `accelerator_name` internally, so the `CString`
+        // only needs to outlive the call below.
+        let name = options
+            .accelerator_name
+            .as_ref()
+            .map(|name| CString::new(name.as_str()).expect("accelerator name has interior NUL"));
+        if let Some(ref name) = name {
+            raw_options.accelerator_name = name.as_ptr();
+        }
+        let ptr = TfLiteNnapiDelegateCreate(&raw_options);
+        if ptr.is_null() {
+            Err(Error::new(ErrorKind::FailedToCreateDelegate))
+        } else {
+            Ok(RawDelegate::new(ptr, TfLiteNnapiDelegateDelete))
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Error and result types produced by this crate.
+use std::fmt;
+
+/// A specialized [`Result`](std::result::Result) type for this crate's operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The kind of error that [`Error`] wraps.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum ErrorKind {
+    /// Failed to load a model, likely because of an invalid path or malformed data.
+    FailedToLoadModel,
+    /// Failed to create the underlying interpreter.
+    FailedToCreateInterpreter,
+    /// Allocating the interpreter's tensors (see `allocate_tensors`) failed.
+    AllocateTensorsRequired,
+    /// Invoking the interpreter failed.
+    FailedToInvoke,
+    /// The given tensor index is out of bounds for the interpreter's inputs or outputs.
+    InvalidTensorIndex(usize, usize),
+    /// Failed to resize the input tensor at the given index.
+    FailedToResizeInputTensor(usize),
+    /// Failed to copy data into a tensor: an interpreter's input tensor, or a custom op's
+    /// output tensor.
+    FailedToCopyDataToInputTensor,
+    /// Failed to retrieve the tensor at the given index.
+    FailedToGetTensor(usize),
+    /// The element count of the provided data does not match the tensor's element count.
+    InvalidTensorDataCount(usize, usize),
+    /// The provided data type does not match the tensor's data type.
+    InvalidTensorDataType,
+    /// [`Interpreter::invoke_with_profile`](crate::interpreter::Interpreter::invoke_with_profile)
+    /// was called without first enabling profiling via
+    /// [`Options::enable_profiling`](crate::interpreter::Options::enable_profiling).
+    ProfilingNotEnabled,
+    /// Failed to create a delegate, e.g. because the requested hardware accelerator is not
+    /// available on this device.
+    FailedToCreateDelegate,
+}
+
+/// An error produced by this crate.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub struct Error {
+    pub kind: ErrorKind,
+}
+
+impl Error {
+    pub(crate) fn new(kind: ErrorKind) -> Self {
+        Error { kind }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.kind)
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,301 @@
+//! The [`Interpreter`] that runs inference for a [`Model`](crate::model::Model), and the
+//! [`Options`] used to configure it.
+use crate::bindings::*;
+#[cfg(feature = "custom_op")]
+use crate::custom_op::CustomOp;
+use crate::delegate::{Delegate, RawDelegate};
+use crate::error::{Error, ErrorKind, Result};
+use crate::model::Model;
+use crate::profile::Profile;
+use crate::tensor::{Shape, Tensor};
+use std::os::raw::c_int;
+use std::time::{Duration, Instant};
+
+const PROFILE_WARMUP_RUNS: usize = 1;
+const PROFILE_TIMED_RUNS: usize = 10;
+
+/// Options for configuring an [`Interpreter`].
+pub struct Options {
+    /// The maximum number of CPU threads that the interpreter should run on. The default value
+    /// is -1, meaning the TF Lite runtime decides the number of threads to use.
+    pub thread_count: i32,
+    /// Whether the XNNPACK delegate should be used to accelerate float32 ops. Requires the
+    /// `xnnpack` feature. Defaults to `true`.
+    #[cfg(feature = "xnnpack")]
+    pub is_xnnpack_enabled: bool,
+    /// When `true`, runs float32 ops through the XNNPACK delegate with reduced fp16
+    /// precision, trading accuracy for latency on hardware with cheap fp16 arithmetic. Has no
+    /// effect unless `is_xnnpack_enabled` is also `true`. Defaults to `false`.
+    #[cfg(feature = "xnnpack")]
+    pub force_fp16: bool,
+    is_profiling_enabled: bool,
+    delegates: Vec<Delegate>,
+    #[cfg(feature = "custom_op")]
+    custom_ops: Vec<CustomOp>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            thread_count: -1,
+            #[cfg(feature = "xnnpack")]
+            is_xnnpack_enabled: true,
+            #[cfg(feature = "xnnpack")]
+            force_fp16: false,
+            is_profiling_enabled: false,
+            delegates: Vec::new(),
+            #[cfg(feature = "custom_op")]
+            custom_ops: Vec::new(),
+        }
+    }
+}
+
+impl Options {
+    /// Registers `delegate` to be bound into the [`Interpreter`] built from these options.
+    ///
+    /// Delegates are tried in the order they were added; ops that an earlier delegate cannot
+    /// handle fall through to the next delegate, and finally to the builtin CPU kernels.
+    pub fn add_delegate(&mut self, delegate: Delegate) -> &mut Self {
+        self.delegates.push(delegate);
+        self
+    }
+
+    /// Enables collecting latency statistics via
+    /// [`Interpreter::invoke_with_profile`](Interpreter::invoke_with_profile).
+    pub fn enable_profiling(&mut self, enabled: bool) -> &mut Self {
+        self.is_profiling_enabled = enabled;
+        self
+    }
+
+    /// Registers `custom_op` so that models containing it can be loaded, even though it is
+    /// not in the builtin op resolver. Requires the `custom_op` feature.
+    #[cfg(feature = "custom_op")]
+    pub fn add_custom_op(&mut self, custom_op: CustomOp) -> &mut Self {
+        self.custom_ops.push(custom_op);
+        self
+    }
+}
+
+/// A TensorFlow Lite interpreter that runs inference using a given [`Model`](crate::model::Model).
+pub struct Interpreter {
+    interpreter: *mut TfLiteInterpreter,
+    // `interpreter` borrows from the model, delegate and custom op fields below for as long as
+    // it is alive, so they must be dropped after it. `Drop for Interpreter` deletes it before
+    // any field is dropped.
+    _model: Model,
+    _delegates: Vec<RawDelegate>,
+    #[cfg(feature = "custom_op")]
+    _custom_ops: Vec<CustomOp>,
+    is_profiling_enabled: bool,
+}
+
+impl Interpreter {
+    /// Creates a new interpreter from the `.tflite` model file at `model_path`.
+    pub fn with_model_path(model_path: &str, options: Option<Options>) -> Result<Interpreter> {
+        let model = Model::new(model_path)?;
+        Interpreter::new(model, options)
+    }
+
+    /// Creates a new interpreter from the bytes of a `.tflite` model already in memory. See
+    /// [`Model::from_bytes`](crate::model::Model::from_bytes).
+    pub fn with_model_bytes(data: &[u8], options: Option<Options>) -> Result<Interpreter> {
+        let model = Model::from_bytes(data)?;
+        Interpreter::new(model, options)
+    }
+
+    fn new(model: Model, options: Option<Options>) -> Result<Interpreter> {
+        let options = options.unwrap_or_default();
+        #[cfg(feature = "xnnpack")]
+        let is_xnnpack_enabled = options.is_xnnpack_enabled;
+        #[cfg(not(feature = "xnnpack"))]
+        let is_xnnpack_enabled = false;
+        #[cfg(feature = "xnnpack")]
+        let force_fp16 = options.force_fp16;
+        #[cfg(not(feature = "xnnpack"))]
+        let force_fp16 = false;
+        let is_profiling_enabled = options.is_profiling_enabled;
+        let mut delegates: Vec<RawDelegate> = options
+            .delegates
+            .into_iter()
+            .map(Delegate::into_raw)
+            .collect::<Result<_>>()?;
+        push_xnnpack_delegate(&mut delegates, is_xnnpack_enabled, force_fp16)?;
+        #[cfg(feature = "custom_op")]
+        let custom_ops = options.custom_ops;
+        unsafe {
+            let options_ptr = TfLiteInterpreterOptionsCreate();
+            TfLiteInterpreterOptionsSetNumThreads(options_ptr, options.thread_count as c_int);
+            for delegate in &delegates {
+                TfLiteInterpreterOptionsAddDelegate(options_ptr, delegate.ptr);
+            }
+            #[cfg(feature = "custom_op")]
+            for custom_op in &custom_ops {
+                TfLiteInterpreterOptionsAddCustomOp(options_ptr, custom_op.registration);
+            }
+            let interpreter_ptr = TfLiteInterpreterCreate(model.model, options_ptr);
+            TfLiteInterpreterOptionsDelete(options_ptr);
+            if interpreter_ptr.is_null() {
+                Err(Error::new(ErrorKind::FailedToCreateInterpreter))
+            } else {
+                Ok(Interpreter {
+                    interpreter: interpreter_ptr,
+                    _model: model,
+                    _delegates: delegates,
+                    #[cfg(feature = "custom_op")]
+                    _custom_ops: custom_ops,
+                    is_profiling_enabled,
+                })
+            }
+        }
+    }
+
+    /// Resizes the input tensor at `index` to `shape`.
+    pub fn resize_input(&self, index: usize, shape: Shape) -> Result<()> {
+        unsafe {
+            let dims: Vec<c_int> = shape.dimensions().iter().map(|&d| d as c_int).collect();
+            let status = TfLiteInterpreterResizeInputTensor(
+                self.interpreter,
+                index as c_int,
+                dims.as_ptr(),
+                dims.len() as c_int,
+            );
+            if status == TfLiteStatus_kTfLiteOk {
+                Ok(())
+            } else {
+                Err(Error::new(ErrorKind::FailedToResizeInputTensor(index)))
+            }
+        }
+    }
+
+    /// Allocates memory for all tensors. Must be called after creating the interpreter and
+    /// after any call to [`resize_input`](Interpreter::resize_input), before [`invoke`](Interpreter::invoke).
+    pub fn allocate_tensors(&self) -> Result<()> {
+        unsafe {
+            if TfLiteInterpreterAllocateTensors(self.interpreter) == TfLiteStatus_kTfLiteOk {
+                Ok(())
+            } else {
+                Err(Error::new(ErrorKind::AllocateTensorsRequired))
+            }
+        }
+    }
+
+    /// Copies `data` into the input tensor at `index`.
+    pub fn copy<T>(&self, data: &[T], index: usize) -> Result<()> {
+        unsafe {
+            let tensor = TfLiteInterpreterGetInputTensor(self.interpreter, index as c_int);
+            if tensor.is_null() {
+                return Err(Error::new(ErrorKind::FailedToGetTensor(index)));
+            }
+            let byte_size = std::mem::size_of_val(data);
+            let status = TfLiteTensorCopyFromBuffer(tensor, data.as_ptr() as *const _, byte_size);
+            if status == TfLiteStatus_kTfLiteOk {
+                Ok(())
+            } else {
+                Err(Error::new(ErrorKind::FailedToCopyDataToInputTensor))
+            }
+        }
+    }
+
+    /// Runs inference on the current input tensors.
+    pub fn invoke(&self) -> Result<()> {
+        unsafe {
+            if TfLiteInterpreterInvoke(self.interpreter) == TfLiteStatus_kTfLiteOk {
+                Ok(())
+            } else {
+                Err(Error::new(ErrorKind::FailedToInvoke))
+            }
+        }
+    }
+
+    /// Runs `invoke` repeatedly (after a short warm-up) and reports wall-clock latency
+    /// statistics, comparable to the upstream `benchmark_model` tool. Requires
+    /// [`Options::enable_profiling`] to have been set to `true` when this interpreter was
+    /// created.
+    pub fn invoke_with_profile(&self) -> Result<Profile> {
+        if !self.is_profiling_enabled {
+            return Err(Error::new(ErrorKind::ProfilingNotEnabled));
+        }
+        for _ in 0..PROFILE_WARMUP_RUNS {
+            self.invoke()?;
+        }
+        let mut runs = Vec::with_capacity(PROFILE_TIMED_RUNS);
+        for _ in 0..PROFILE_TIMED_RUNS {
+            let start = Instant::now();
+            self.invoke()?;
+            runs.push(start.elapsed());
+        }
+        let total: Duration = runs.iter().sum();
+        let mean = total / runs.len() as u32;
+        let min = *runs.iter().min().expect("at least one timed run");
+        let max = *runs.iter().max().expect("at least one timed run");
+        Ok(Profile {
+            min,
+            max,
+            mean,
+            runs,
+        })
+    }
+
+    /// Returns the input tensor at `index`.
+    pub fn input(&self, index: usize) -> Result<Tensor<'_>> {
+        unsafe {
+            let tensor = TfLiteInterpreterGetInputTensor(self.interpreter, index as c_int);
+            if tensor.is_null() {
+                Err(Error::new(ErrorKind::FailedToGetTensor(index)))
+            } else {
+                Ok(Tensor::new(tensor))
+            }
+        }
+    }
+
+    /// Returns the output tensor at `index`.
+    pub fn output(&self, index: usize) -> Result<Tensor<'_>> {
+        unsafe {
+            let tensor = TfLiteInterpreterGetOutputTensor(self.interpreter, index as c_int);
+            if tensor.is_null() {
+                Err(Error::new(ErrorKind::FailedToGetTensor(index)))
+            } else {
+                Ok(Tensor::new(tensor))
+            }
+        }
+    }
+}
+
+impl Drop for Interpreter {
+    fn drop(&mut self) {
+        unsafe { TfLiteInterpreterDelete(self.interpreter) }
+    }
+}
+
+unsafe impl Send for Interpreter {}
+
+#[cfg(feature = "xnnpack")]
+fn push_xnnpack_delegate(
+    delegates: &mut Vec<RawDelegate>,
+    enabled: bool,
+    force_fp16: bool,
+) -> Result<()> {
+    if enabled {
+        unsafe {
+            let mut xnnpack_options = TfLiteXNNPackDelegateOptionsDefault();
+            if force_fp16 {
+                xnnpack_options.flags |= TFLITE_XNNPACK_DELEGATE_FLAG_FORCE_FP16 as i32;
+            }
+            let xnnpack_ptr = TfLiteXNNPackDelegateCreate(&xnnpack_options);
+            if xnnpack_ptr.is_null() {
+                return Err(Error::new(ErrorKind::FailedToCreateDelegate));
+            }
+            delegates.push(RawDelegate::new(xnnpack_ptr, TfLiteXNNPackDelegateDelete));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "xnnpack"))]
+fn push_xnnpack_delegate(
+    _delegates: &mut Vec<RawDelegate>,
+    _enabled: bool,
+    _force_fp16: bool,
+) -> Result<()> {
+    Ok(())
+}
@@ -25,6 +25,16 @@
 //! Implies `xnnpack`.
 //! * `xnnpack_qu8` - Similar to `xnnpack_qs8`, but accelerates few operators with
 //! asymmetric quantization. Implies `xnnpack`.
+//! * `gpu_delegate` - Adds bindings for the GPU delegate, see [`delegate::Delegate::Gpu`].
+//! * `nnapi_delegate` - Adds bindings for the Android NNAPI delegate, see
+//!   [`delegate::Delegate::NnApi`].
+//! * `coreml_delegate` - Adds bindings for the iOS Core ML delegate, see
+//!   [`delegate::Delegate::CoreMl`]. None of the delegate features changes the Bazel build;
+//!   see [`delegate`].
+//! * `custom_op` - Enables registering custom (non-builtin) ops, see [`custom_op::CustomOp`].
+//!   Builds against TensorFlow `r2.13` instead of the default `r2.6`, since the underlying
+//!   opaque operator C API does not exist until then.
+//! * `serde` - Derives `Serialize` for [`profile::Profile`].
 //!
 //! *Note:* `xnnpack` is already enabled for iOS, but `xnnpack_qs8` and `xnnpack_qu8`
 //! should be enabled manually.
@@ -89,6 +99,14 @@
 //! BAZEL_COPTS="OPT1 OPT2 ..." # space seperated values will be passed as `--copt=OPTN` to bazel
 //! BAZEL_COPTS="-march=native" # for native optimized build
 //! ```
+//!
+//! ## Linking Against a Prebuilt Library
+//! If you already have a compiled `libtensorflowlite_c` (from CMake, a system package, or a
+//! CI cache), you can skip the Bazel build entirely by setting:
+//! ```sh
+//! TFLITEC_PREBUILT_LIB_DIR=/path/to/dir/containing/libtensorflowlite_c
+//! TFLITEC_HEADER_DIR=/path/to/tensorflow # optional, defaults to TFLITEC_PREBUILT_LIB_DIR
+//! ```
 //! ---
 //! Some OSs or targets may require additional steps.
 //!
@@ -127,9 +145,13 @@
 //! [cargo documentation]: https://doc.rust-lang.org/cargo/reference/environment-variables.html#environment-variables-cargo-sets-for-crates
 //! [cargo-ndk]: https://github.com/bbqsrc/cargo-ndk
 
+#[cfg(feature = "custom_op")]
+pub mod custom_op;
+pub mod delegate;
 mod error;
 pub mod interpreter;
 pub mod model;
+pub mod profile;
 pub mod tensor;
 
 pub(crate) mod bindings {
@@ -0,0 +1,55 @@
+//! A TensorFlow Lite model, the input to an [`Interpreter`](crate::interpreter::Interpreter).
+use crate::bindings::TfLiteModel;
+use crate::error::{Error, ErrorKind, Result};
+use std::ffi::CString;
+use std::os::raw::c_void;
+
+/// A loaded TensorFlow Lite model.
+pub struct Model {
+    pub(crate) model: *mut TfLiteModel,
+    // Only populated by `from_bytes`, to keep the buffer `TfLiteModelCreate` points into alive
+    // for as long as the model (and, in turn, any interpreter built from it) is alive.
+    _data: Vec<u8>,
+}
+
+impl Model {
+    /// Creates a new `Model` by reading the `.tflite` file at `filepath`.
+    pub fn new(filepath: &str) -> Result<Model> {
+        let path = CString::new(filepath).map_err(|_| Error::new(ErrorKind::FailedToLoadModel))?;
+        let model = unsafe { crate::bindings::TfLiteModelCreateFromFile(path.as_ptr()) };
+        if model.is_null() {
+            Err(Error::new(ErrorKind::FailedToLoadModel))
+        } else {
+            Ok(Model {
+                model,
+                _data: Vec::new(),
+            })
+        }
+    }
+
+    /// Creates a new `Model` from the bytes of a `.tflite` file already in memory, e.g. one
+    /// received over the network, embedded with `include_bytes!`, or decrypted in memory.
+    ///
+    /// `TfLiteModelCreate` does not copy `data`, so `data` is copied into the returned `Model`
+    /// once here to keep it alive for as long as the model needs it.
+    pub fn from_bytes(data: &[u8]) -> Result<Model> {
+        let data = data.to_vec();
+        let model = unsafe {
+            crate::bindings::TfLiteModelCreate(data.as_ptr() as *const c_void, data.len())
+        };
+        if model.is_null() {
+            Err(Error::new(ErrorKind::FailedToLoadModel))
+        } else {
+            Ok(Model { model, _data: data })
+        }
+    }
+}
+
+impl Drop for Model {
+    fn drop(&mut self) {
+        unsafe { crate::bindings::TfLiteModelDelete(self.model) }
+    }
+}
+
+unsafe impl Send for Model {}
+unsafe impl Sync for Model {}
@@ -0,0 +1,22 @@
+//! Latency statistics produced by [`Interpreter::invoke_with_profile`](crate::interpreter::Interpreter::invoke_with_profile).
+use std::time::Duration;
+
+/// Wall-clock latency statistics gathered from several back-to-back `invoke` calls.
+///
+/// The TF Lite C API does not expose per-operator timing, so this is measured by timing
+/// repeated `invoke` calls (after a warm-up) rather than hooking an internal profiler.
+///
+/// Implements `Serialize` when the crate's `serde` feature is enabled, so a profile can be
+/// dumped after running representative inputs.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Profile {
+    /// Latency of the fastest timed run.
+    pub min: Duration,
+    /// Latency of the slowest timed run.
+    pub max: Duration,
+    /// Average latency across all timed runs.
+    pub mean: Duration,
+    /// Latency of every timed run, in the order they ran.
+    pub runs: Vec<Duration>,
+}
@@ -0,0 +1,128 @@
+//! Types describing the input/output tensors of an
+//! [`Interpreter`](crate::interpreter::Interpreter).
+use crate::bindings::*;
+use std::marker::PhantomData;
+use std::os::raw::c_char;
+use std::slice;
+
+/// The data type of a [`Tensor`]'s elements.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum DataType {
+    NoType,
+    Float32,
+    Int32,
+    UInt8,
+    Int64,
+    String,
+    Bool,
+    Int16,
+    Complex64,
+    Int8,
+    Float16,
+    Float64,
+}
+
+#[allow(non_upper_case_globals)]
+impl From<TfLiteType> for DataType {
+    fn from(tflite_type: TfLiteType) -> Self {
+        match tflite_type {
+            TfLiteType_kTfLiteFloat32 => DataType::Float32,
+            TfLiteType_kTfLiteInt32 => DataType::Int32,
+            TfLiteType_kTfLiteUInt8 => DataType::UInt8,
+            TfLiteType_kTfLiteInt64 => DataType::Int64,
+            TfLiteType_kTfLiteString => DataType::String,
+            TfLiteType_kTfLiteBool => DataType::Bool,
+            TfLiteType_kTfLiteInt16 => DataType::Int16,
+            TfLiteType_kTfLiteComplex64 => DataType::Complex64,
+            TfLiteType_kTfLiteInt8 => DataType::Int8,
+            TfLiteType_kTfLiteFloat16 => DataType::Float16,
+            TfLiteType_kTfLiteFloat64 => DataType::Float64,
+            _ => DataType::NoType,
+        }
+    }
+}
+
+/// The shape of a [`Tensor`], i.e. the size of each of its dimensions.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Shape {
+    dimensions: Vec<usize>,
+}
+
+impl Shape {
+    /// Creates a new shape with the given dimension sizes.
+    pub fn new(dimensions: Vec<usize>) -> Shape {
+        Shape { dimensions }
+    }
+
+    /// The number of dimensions of this shape.
+    pub fn rank(&self) -> usize {
+        self.dimensions.len()
+    }
+
+    /// The size of each dimension, outermost first.
+    pub fn dimensions(&self) -> &Vec<usize> {
+        &self.dimensions
+    }
+}
+
+/// An input or output tensor of an [`Interpreter`](crate::interpreter::Interpreter).
+///
+/// The lifetime of a `Tensor` is tied to the interpreter that produced it, since the
+/// underlying buffer is owned by the interpreter.
+pub struct Tensor<'a> {
+    pub(crate) tensor: *const TfLiteTensor,
+    pub(crate) _interpreter: PhantomData<&'a ()>,
+}
+
+impl<'a> Tensor<'a> {
+    pub(crate) fn new(tensor: *const TfLiteTensor) -> Tensor<'a> {
+        Tensor {
+            tensor,
+            _interpreter: PhantomData,
+        }
+    }
+
+    /// The name of this tensor.
+    pub fn name(&self) -> &str {
+        unsafe {
+            let name = TfLiteTensorName(self.tensor);
+            std::ffi::CStr::from_ptr(name as *const c_char)
+                .to_str()
+                .unwrap_or_default()
+        }
+    }
+
+    /// The data type of this tensor's elements.
+    pub fn data_type(&self) -> DataType {
+        unsafe { TfLiteTensorType(self.tensor).into() }
+    }
+
+    /// The shape of this tensor.
+    pub fn shape(&self) -> Shape {
+        unsafe {
+            let rank = TfLiteTensorNumDims(self.tensor);
+            let dimensions = (0..rank)
+                .map(|i| TfLiteTensorDim(self.tensor, i) as usize)
+                .collect();
+            Shape::new(dimensions)
+        }
+    }
+
+    /// The contents of this tensor, reinterpreted as a slice of `T`.
+    ///
+    /// # Panics
+    /// Panics if the byte size of this tensor's buffer is not a multiple of `size_of::<T>()`.
+    pub fn data<T>(&self) -> &[T] {
+        unsafe {
+            let data = TfLiteTensorData(self.tensor) as *const T;
+            let byte_size = TfLiteTensorByteSize(self.tensor);
+            assert_eq!(
+                byte_size % std::mem::size_of::<T>(),
+                0,
+                "tensor byte size is not a multiple of the element size"
+            );
+            let count = byte_size / std::mem::size_of::<T>();
+            slice::from_raw_parts(data, count)
+        }
+    }
+}